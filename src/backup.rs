@@ -1,13 +1,46 @@
+use crate::bundle::{self, bundle_path};
 use crate::github::{Repo, run_cmd};
+use crate::manifest::{self, ArtifactKind, Manifest, ManifestEntry};
+use crate::s3::S3Config;
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+/// How each repo is materialized on disk.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `git clone --mirror` / `git remote update`
+    Mirror,
+    /// Regular working clone / `git fetch --all`
+    Regular,
+    /// `git bundle create <name>-<timestamp>.bundle --all`, backed by a
+    /// hidden mirror cache that's fetched incrementally between runs.
+    Bundle,
+}
+
+impl Format {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Format::Mirror => "mirror",
+            Format::Regular => "regular",
+            Format::Bundle => "bundle",
+        }
+    }
+}
+
 pub enum Status {
     Cloned,
     Updated,
     Failed,
+    Bundled,
+    /// Found healthy by `repair`, no action needed
+    Healthy,
+    /// Found broken by `repair` and fixed (re-fetched or re-cloned)
+    Repaired,
+    /// Objects passed `fsck`, but the remote fetch itself failed — a
+    /// transient/network issue, distinct from actual corruption
+    FetchStale,
 }
 
 impl Status {
@@ -16,6 +49,10 @@ impl Status {
             Status::Cloned => "+",
             Status::Updated => "~",
             Status::Failed => "!",
+            Status::Bundled => "b",
+            Status::Healthy => "=",
+            Status::Repaired => "r",
+            Status::FetchStale => "s",
         }
     }
 }
@@ -24,15 +61,146 @@ pub struct BackupResult {
     pub nwo: String,
     pub status: Status,
     pub error: Option<String>,
+    /// Upload outcome, tracked separately from the git operation above —
+    /// `None` when no S3 sink was configured.
+    pub upload_error: Option<String>,
+    /// Integrity manifest entry for this repo's artifact, if it was
+    /// produced successfully.
+    pub manifest_entry: Option<ManifestEntry>,
+    /// Manifest entries for any `.ghsync/*` metadata files exported for
+    /// this repo, keyed by `<nwo>#<file>`.
+    pub metadata_entries: Vec<(String, ManifestEntry)>,
 }
 
-fn backup_repo(repo: &Repo, dest: &Path, mirror: bool) -> BackupResult {
+fn backup_repo(
+    repo: &Repo,
+    dest: &Path,
+    format: Format,
+    s3: Option<&S3Config>,
+    include_metadata: bool,
+    include_wiki: bool,
+) -> BackupResult {
     let nwo = repo.name_with_owner.as_str();
     let (owner, name) = nwo.split_once('/').unwrap_or(("", nwo));
+
+    let result = if format == Format::Bundle {
+        bundle_repo(nwo, owner, name, dest)
+    } else {
+        mirror_or_clone(nwo, owner, name, dest, format == Format::Mirror)
+    };
+
+    let (status, error, artifact) = match result {
+        Ok((status, artifact)) => (status, None, Some(artifact)),
+        Err(e) => (Status::Failed, Some(e), None),
+    };
+
+    let upload_error = match (s3, &artifact) {
+        (Some(s3), Some(artifact)) => upload_artifact(s3, artifact, owner, name, format)
+            .err()
+            .map(|e| e.to_string()),
+        _ => None,
+    };
+
+    let manifest_entry = artifact
+        .as_deref()
+        .and_then(|artifact| manifest_entry_for(artifact, dest, format).ok());
+
+    let metadata_entries = if include_metadata && artifact.is_some() {
+        export_metadata_entries(repo, dest, owner, name, include_wiki)
+    } else {
+        Vec::new()
+    };
+
+    BackupResult {
+        nwo: nwo.to_string(),
+        status,
+        error,
+        upload_error,
+        manifest_entry,
+        metadata_entries,
+    }
+}
+
+fn export_metadata_entries(
+    repo: &Repo,
+    dest: &Path,
+    owner: &str,
+    name: &str,
+    include_wiki: bool,
+) -> Vec<(String, ManifestEntry)> {
+    let repo_dir = dest.join(owner).join(name);
+    let files = match crate::github::export_metadata(repo, &repo_dir, include_wiki) {
+        Ok(files) => files,
+        Err(e) => {
+            println!("           metadata export failed: {e}");
+            return Vec::new();
+        }
+    };
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let integrity = manifest::sri_of_file(&path).ok()?;
+            let rel = path.strip_prefix(dest).unwrap_or(&path).to_path_buf();
+            let file_label = path.file_name()?.to_string_lossy().to_string();
+            Some((
+                format!("{owner}/{name}#{file_label}"),
+                ManifestEntry {
+                    path: rel,
+                    kind: ArtifactKind::Metadata,
+                    integrity,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn manifest_entry_for(artifact: &Path, dest: &Path, format: Format) -> Result<ManifestEntry> {
+    let kind = if format == Format::Bundle {
+        ArtifactKind::Bundle
+    } else {
+        ArtifactKind::Clone
+    };
+    let integrity = match kind {
+        ArtifactKind::Bundle | ArtifactKind::Metadata => manifest::sri_of_file(artifact)?,
+        ArtifactKind::Clone => manifest::sri_of_clone(artifact)?,
+    };
+    let path = artifact
+        .strip_prefix(dest)
+        .unwrap_or(artifact)
+        .to_path_buf();
+    Ok(ManifestEntry {
+        path,
+        kind,
+        integrity,
+    })
+}
+
+fn upload_artifact(
+    s3: &S3Config,
+    artifact: &Path,
+    owner: &str,
+    name: &str,
+    format: Format,
+) -> Result<()> {
+    if format == Format::Bundle {
+        s3.upload(artifact, owner, name, ".bundle")
+    } else {
+        s3.upload_dir(artifact, owner, name)
+    }
+}
+
+fn mirror_or_clone(
+    nwo: &str,
+    owner: &str,
+    name: &str,
+    dest: &Path,
+    mirror: bool,
+) -> std::result::Result<(Status, PathBuf), String> {
     let repo_dir = dest.join(owner).join(name);
     let repo_dir_str = repo_dir.display().to_string();
 
-    let result: std::result::Result<Status, String> = if repo_dir.exists() {
+    let status = if repo_dir.exists() {
         let cmd = if mirror {
             vec!["git", "-C", repo_dir_str.as_str(), "remote", "update"]
         } else {
@@ -40,39 +208,48 @@ fn backup_repo(repo: &Repo, dest: &Path, mirror: bool) -> BackupResult {
         };
         run_cmd(&cmd)
             .map(|_| Status::Updated)
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?
     } else {
         if let Some(parent) = repo_dir.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
-        let mut args = vec![
-            "gh",
-            "repo",
-            "clone",
-            nwo,
-            repo_dir_str.as_str(),
-            "--",
-        ];
+        let mut args = vec!["gh", "repo", "clone", nwo, repo_dir_str.as_str(), "--"];
         if mirror {
             args.push("--mirror");
         }
         run_cmd(&args)
             .map(|_| Status::Cloned)
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?
     };
 
-    match result {
-        Ok(status) => BackupResult {
-            nwo: nwo.to_string(),
-            status,
-            error: None,
-        },
-        Err(e) => BackupResult {
-            nwo: nwo.to_string(),
-            status: Status::Failed,
-            error: Some(e),
-        },
+    Ok((status, repo_dir))
+}
+
+/// Fetches into a hidden bare mirror cache, then bundles it to a
+/// timestamped, portable artifact under `dest/<owner>/`.
+fn bundle_repo(
+    nwo: &str,
+    owner: &str,
+    name: &str,
+    dest: &Path,
+) -> std::result::Result<(Status, PathBuf), String> {
+    let cache_dir = dest.join(".ghsync-cache").join(owner).join(name);
+    let cache_dir_str = cache_dir.display().to_string();
+
+    if cache_dir.exists() {
+        run_cmd(&["git", "-C", cache_dir_str.as_str(), "remote", "update"])
+            .map_err(|e| e.to_string())?;
+    } else {
+        if let Some(parent) = cache_dir.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        run_cmd(&["gh", "repo", "clone", nwo, cache_dir_str.as_str(), "--", "--mirror"])
+            .map_err(|e| e.to_string())?;
     }
+
+    let path = bundle_path(dest, owner, name, bundle::unix_timestamp());
+    bundle::create_bundle(&cache_dir, &path).map_err(|e| e.to_string())?;
+    Ok((Status::Bundled, path))
 }
 
 struct Progress {
@@ -80,13 +257,21 @@ struct Progress {
     results: Vec<BackupResult>,
 }
 
-pub fn run_backup(repos: &[Repo], dest: &Path, mirror: bool, jobs: usize) -> Result<()> {
+pub fn run_backup(
+    repos: &[Repo],
+    dest: &Path,
+    format: Format,
+    jobs: usize,
+    s3: Option<&S3Config>,
+    include_metadata: bool,
+    include_wiki: bool,
+) -> Result<()> {
     std::fs::create_dir_all(dest)?;
     let dest = std::fs::canonicalize(dest)?;
-    let mode = if mirror { "mirror" } else { "regular" };
     println!(
-        "\nBacking up to: {} (mode: {mode}, workers: {jobs})",
-        dest.display()
+        "\nBacking up to: {} (mode: {}, workers: {jobs})",
+        dest.display(),
+        format.label()
     );
 
     let total = repos.len();
@@ -103,7 +288,14 @@ pub fn run_backup(repos: &[Repo], dest: &Path, mirror: bool, jobs: usize) -> Res
                 if idx >= total {
                     break;
                 }
-                let result = backup_repo(&repos[idx], &dest, mirror);
+                let result = backup_repo(
+                    &repos[idx],
+                    &dest,
+                    format,
+                    s3,
+                    include_metadata,
+                    include_wiki,
+                );
                 let mut p = progress.lock().unwrap();
                 p.count += 1;
                 let i = p.count;
@@ -113,6 +305,9 @@ pub fn run_backup(repos: &[Repo], dest: &Path, mirror: bool, jobs: usize) -> Res
                         println!("           {first_line}");
                     }
                 }
+                if let Some(ref e) = result.upload_error {
+                    println!("           upload failed: {e}");
+                }
                 p.results.push(result);
             });
         }
@@ -127,15 +322,44 @@ pub fn run_backup(repos: &[Repo], dest: &Path, mirror: bool, jobs: usize) -> Res
         .iter()
         .filter(|r| matches!(r.status, Status::Updated))
         .count();
+    let bundled = results
+        .iter()
+        .filter(|r| matches!(r.status, Status::Bundled))
+        .count();
     let failed: Vec<_> = results
         .iter()
         .filter(|r| matches!(r.status, Status::Failed))
         .collect();
+    let upload_failed: Vec<_> = results
+        .iter()
+        .filter(|r| r.upload_error.is_some())
+        .collect();
 
     println!("\n--- Summary ---");
     println!("  Cloned:  {cloned}");
     println!("  Updated: {updated}");
+    if bundled > 0 {
+        println!("  Bundled: {bundled}");
+    }
     println!("  Failed:  {}", failed.len());
+    if s3.is_some() {
+        println!("  Upload failed: {}", upload_failed.len());
+    }
+
+    // Merge onto the existing manifest rather than overwriting it, so repos
+    // outside this run's scope (or that failed this run, before an entry
+    // was produced) keep their previously-recorded entries.
+    let mut manifest = manifest::load(&dest).unwrap_or_default();
+    for r in &results {
+        if let Some(entry) = &r.manifest_entry {
+            manifest.entries.insert(r.nwo.clone(), entry.clone());
+        }
+        for (key, entry) in &r.metadata_entries {
+            manifest.entries.insert(key.clone(), entry.clone());
+        }
+    }
+    manifest::write(&dest, &manifest)?;
+    println!("  Manifest: {}", dest.join(manifest::MANIFEST_FILE).display());
 
     if !failed.is_empty() {
         println!("\nFailed repos:");
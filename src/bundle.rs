@@ -0,0 +1,142 @@
+use crate::github::run_cmd;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Path for a timestamped bundle artifact: `<dest>/<owner>/<name>-<ts>.bundle`.
+pub fn bundle_path(dest: &Path, owner: &str, name: &str, timestamp: u64) -> PathBuf {
+    dest.join(owner).join(format!("{name}-{timestamp}.bundle"))
+}
+
+/// Creates a self-contained `git bundle` of every ref in `repo_dir`.
+pub fn create_bundle(repo_dir: &Path, bundle_path: &Path) -> Result<()> {
+    if let Some(parent) = bundle_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    run_cmd(&[
+        "git",
+        "-C",
+        &repo_dir.display().to_string(),
+        "bundle",
+        "create",
+        &bundle_path.display().to_string(),
+        "--all",
+    ])?;
+    Ok(())
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// One `.bundle` artifact discovered on disk, parsed back into its
+/// `owner`, `name` and `timestamp`.
+struct BundleFile {
+    path: PathBuf,
+    owner: String,
+    name: String,
+    timestamp: u64,
+    bytes: u64,
+}
+
+fn scan_bundles(dest: &Path) -> Result<Vec<BundleFile>> {
+    let mut found = Vec::new();
+    let Ok(owner_dirs) = fs::read_dir(dest) else {
+        return Ok(found);
+    };
+
+    for owner_entry in owner_dirs.flatten() {
+        if !owner_entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let owner = owner_entry.file_name().to_string_lossy().to_string();
+
+        for entry in fs::read_dir(owner_entry.path())?.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("bundle") {
+                continue;
+            }
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            let Some((name, ts)) = stem.rsplit_once('-') else {
+                continue;
+            };
+            let Ok(timestamp) = ts.parse::<u64>() else {
+                continue;
+            };
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            found.push(BundleFile {
+                path,
+                owner,
+                name: name.to_string(),
+                timestamp,
+                bytes,
+            });
+        }
+    }
+
+    Ok(found)
+}
+
+/// Either keep the `keep` most recent bundles per repo, or keep bundles
+/// newer than `older_than` — mutually exclusive strategies, mirroring the
+/// rest of ghsync's filter flags.
+pub struct PruneOptions {
+    pub keep: Option<usize>,
+    pub older_than: Option<Duration>,
+}
+
+/// Deletes bundles that fall outside `opts`, printing what was removed and
+/// returning the total bytes reclaimed.
+pub fn prune(dest: &Path, opts: &PruneOptions) -> Result<u64> {
+    let mut bundles = scan_bundles(dest)?;
+    bundles.sort_by(|a, b| {
+        (&a.owner, &a.name, b.timestamp).cmp(&(&b.owner, &b.name, a.timestamp))
+    });
+
+    let cutoff = opts.older_than.map(|d| {
+        unix_timestamp().saturating_sub(d.as_secs())
+    });
+
+    let mut reclaimed = 0u64;
+    let mut kept_per_repo: std::collections::HashMap<(String, String), usize> =
+        std::collections::HashMap::new();
+
+    for bundle in &bundles {
+        let key = (bundle.owner.clone(), bundle.name.clone());
+        let seen_so_far = *kept_per_repo.get(&key).unwrap_or(&0);
+
+        let keep = match (opts.keep, cutoff) {
+            (Some(n), _) => seen_so_far < n,
+            (None, Some(cutoff)) => bundle.timestamp >= cutoff,
+            (None, None) => true,
+        };
+
+        if keep {
+            kept_per_repo.insert(key, seen_so_far + 1);
+            continue;
+        }
+
+        match fs::remove_file(&bundle.path) {
+            Ok(()) => {
+                reclaimed += bundle.bytes;
+                println!(
+                    "  pruned {}/{} ({} bytes)",
+                    bundle.owner,
+                    bundle.path.file_name().unwrap_or_default().to_string_lossy(),
+                    bundle.bytes
+                );
+            }
+            Err(e) => println!(
+                "  failed to prune {}: {e}",
+                bundle.path.display()
+            ),
+        }
+    }
+
+    Ok(reclaimed)
+}
@@ -1,8 +1,15 @@
-use crate::backup::run_backup;
-use crate::github::{self, Filters};
+use crate::backup::{run_backup, Format};
+use crate::bundle::{self, PruneOptions};
+use crate::config;
+use crate::github::{self, Filters, OrgFilters};
+use crate::manifest::{self, VerifyOutcome};
+use crate::repair;
+use crate::s3::S3Config;
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(
@@ -17,9 +24,18 @@ use std::path::PathBuf;
     ghsync --match "tda-*"                    Repos matching glob
     ghsync --exclude "poc-*" --no-archived    Skip POCs and archived
     ghsync --visibility private               Only private repos
-    ghsync --list-orgs                        Show orgs and exit"#
+    ghsync --list-orgs                        Show orgs and exit
+    ghsync --format bundle                    Back up as portable .bundle files
+    ghsync prune --dest ~/backup --keep 5     Keep 5 newest bundles per repo
+    ghsync --s3-bucket my-backups             Also upload each artifact to S3
+    ghsync verify --dest ~/backup             Check artifacts against the manifest
+    ghsync repair --dest ~/backup             Re-clone any corrupt mirrors
+    ghsync --include-metadata --include-wiki  Also back up issues/PRs/wiki"#
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Back up specific org(s) only (repeatable)
     #[arg(long, value_name = "NAME", help_heading = "Scope")]
     org: Vec<String>,
@@ -65,25 +81,135 @@ struct Cli {
     exclude: Vec<String>,
 
     /// Destination directory
-    #[arg(long, default_value = ".", help_heading = "Clone Options")]
-    dest: PathBuf,
+    #[arg(long, help_heading = "Clone Options")]
+    dest: Option<PathBuf>,
 
     /// Use regular clone instead of --mirror
-    #[arg(long, help_heading = "Clone Options")]
+    #[arg(long, conflicts_with = "format", help_heading = "Clone Options")]
     no_mirror: bool,
 
+    /// Archive format for each repo
+    #[arg(long, value_parser = ["mirror", "bundle"], help_heading = "Clone Options")]
+    format: Option<String>,
+
     /// Parallel workers
-    #[arg(long, default_value_t = 4, help_heading = "Clone Options")]
-    jobs: usize,
+    #[arg(long, help_heading = "Clone Options")]
+    jobs: Option<usize>,
 
     /// List repos without cloning
     #[arg(long)]
     dry_run: bool,
+
+    /// Path to a ghsync.toml/ghsync.yaml config file (default: discovered in CWD)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// S3-compatible endpoint to upload to, e.g. Garage/MinIO (default: AWS)
+    #[arg(long, value_name = "URL", help_heading = "Remote Sink")]
+    s3_endpoint: Option<String>,
+
+    /// Bucket to upload backups to
+    #[arg(long, value_name = "NAME", help_heading = "Remote Sink")]
+    s3_bucket: Option<String>,
+
+    /// Also export issues, PRs, releases, labels, and repo details
+    #[arg(long, help_heading = "Metadata")]
+    include_metadata: bool,
+
+    /// Also export the wiki (implies --include-metadata)
+    #[arg(long, help_heading = "Metadata")]
+    include_wiki: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Delete old bundle artifacts, keeping only the most recent ones
+    Prune(PruneArgs),
+    /// Check backed-up artifacts against the integrity manifest
+    Verify(VerifyArgs),
+    /// Detect and re-clone corrupt or stale mirrors
+    Repair(RepairArgs),
+}
+
+#[derive(clap::Args)]
+struct RepairArgs {
+    /// Backup destination to walk and repair
+    #[arg(long)]
+    dest: PathBuf,
+
+    /// Parallel workers
+    #[arg(long, default_value_t = 4)]
+    jobs: usize,
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    /// Backup destination containing the manifest to check
+    #[arg(long)]
+    dest: PathBuf,
+}
+
+fn run_verify(args: &VerifyArgs) -> Result<()> {
+    let manifest = manifest::load(&args.dest)?;
+    println!("Verifying {} repo(s)...", manifest.entries.len());
+
+    let mut failures = 0;
+    for (nwo, entry) in &manifest.entries {
+        let outcome = manifest::verify_entry(&args.dest, entry);
+        if !matches!(outcome, VerifyOutcome::Ok) {
+            failures += 1;
+        }
+        println!("  [{}] {nwo}", outcome.label());
+    }
+
+    println!("\n--- Summary ---");
+    println!("  Checked: {}", manifest.entries.len());
+    println!("  Failed:  {failures}");
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[derive(clap::Args)]
+struct PruneArgs {
+    /// Backup destination to prune bundles under
+    #[arg(long)]
+    dest: PathBuf,
+
+    /// Keep this many most-recent bundles per repo
+    #[arg(long, conflicts_with = "older_than")]
+    keep: Option<usize>,
+
+    /// Keep bundles newer than this duration (e.g. "30d", "12h")
+    #[arg(long, value_parser = humantime::parse_duration, conflicts_with = "keep")]
+    older_than: Option<Duration>,
+}
+
+fn run_prune(args: &PruneArgs) -> Result<()> {
+    println!("Pruning bundles in: {}", args.dest.display());
+    let opts = PruneOptions {
+        keep: args.keep,
+        older_than: args.older_than,
+    };
+    let reclaimed = bundle::prune(&args.dest, &opts)?;
+    println!("\nReclaimed {reclaimed} bytes");
+    Ok(())
 }
 
 pub fn run() -> Result<()> {
     let args = Cli::parse();
 
+    match &args.command {
+        Some(Command::Prune(prune_args)) => return run_prune(prune_args),
+        Some(Command::Verify(verify_args)) => return run_verify(verify_args),
+        Some(Command::Repair(repair_args)) => return repair::run_repair(&repair_args.dest, repair_args.jobs),
+        None => {}
+    }
+
+    let config = config::load(args.config.as_deref())?;
+
     github::check_gh()?;
     let username = github::get_username()?;
     println!("Authenticated as: {username}");
@@ -101,17 +227,41 @@ pub fn run() -> Result<()> {
         return Ok(());
     }
 
+    let defaults = config.as_ref().map(|c| &c.defaults);
+    let per_owner: HashMap<String, OrgFilters> = config
+        .as_ref()
+        .map(|c| c.org_filters())
+        .unwrap_or_default();
+    let visibility = args
+        .visibility
+        .clone()
+        .or_else(|| defaults.and_then(|d| d.visibility.clone()));
+
+    // `--org` overrides the config; with neither set, fall back to every
+    // org declared in `organizations` so a config-only setup doesn't also
+    // need `--org` passed on every run.
+    let config_orgs: Vec<String> = config
+        .as_ref()
+        .map(|c| c.organizations.iter().map(|o| o.name.clone()).collect())
+        .unwrap_or_default();
+    let org: &[String] = if !args.org.is_empty() {
+        &args.org
+    } else {
+        &config_orgs
+    };
+
     let filters = Filters {
-        org: &args.org,
+        org,
         orgs_only: args.orgs_only,
         personal_only: args.personal_only,
-        no_forks: args.no_forks,
+        no_forks: args.no_forks || defaults.and_then(|d| d.no_forks).unwrap_or(false),
         forks_only: args.forks_only,
-        no_archived: args.no_archived,
+        no_archived: args.no_archived || defaults.and_then(|d| d.no_archived).unwrap_or(false),
         archived_only: args.archived_only,
-        visibility: args.visibility.as_deref(),
+        visibility: visibility.as_deref(),
         patterns: &args.patterns,
         exclude: &args.exclude,
+        per_owner: &per_owner,
     };
     let repos = github::discover_repos(&filters, &username, &orgs)?;
 
@@ -146,8 +296,35 @@ pub fn run() -> Result<()> {
         return Ok(());
     }
 
-    let mirror = !args.no_mirror;
-    run_backup(&repos, &args.dest, mirror, args.jobs)?;
+    let dest = args
+        .dest
+        .or_else(|| config.as_ref().and_then(|c| c.destination.clone()))
+        .unwrap_or_else(|| PathBuf::from("."));
+    let mirror = !args.no_mirror && defaults.and_then(|d| d.mirror).unwrap_or(true);
+    let format = match args.format.as_deref() {
+        Some("bundle") => Format::Bundle,
+        Some("mirror") => Format::Mirror,
+        _ if mirror => Format::Mirror,
+        _ => Format::Regular,
+    };
+    let jobs = args
+        .jobs
+        .or_else(|| defaults.and_then(|d| d.jobs))
+        .unwrap_or(4);
+    let s3 = args.s3_bucket.as_ref().map(|bucket| S3Config {
+        endpoint: args.s3_endpoint.clone(),
+        bucket: bucket.clone(),
+    });
+    let include_metadata = args.include_metadata || args.include_wiki;
+    run_backup(
+        &repos,
+        &dest,
+        format,
+        jobs,
+        s3.as_ref(),
+        include_metadata,
+        args.include_wiki,
+    )?;
 
     Ok(())
 }
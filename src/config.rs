@@ -0,0 +1,95 @@
+use crate::github::OrgFilters;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Declarative backup profile, loaded from `ghsync.toml` (or `ghsync.yaml`)
+/// so a multi-org setup doesn't have to be re-typed as flags on every run.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    pub destination: Option<PathBuf>,
+    #[serde(default)]
+    pub defaults: Defaults,
+    #[serde(default)]
+    pub organizations: Vec<OrgEntry>,
+}
+
+/// Global fallbacks, used whenever a flag isn't passed on the command line.
+#[derive(Debug, Default, Deserialize)]
+pub struct Defaults {
+    pub mirror: Option<bool>,
+    pub jobs: Option<usize>,
+    pub no_forks: Option<bool>,
+    pub no_archived: Option<bool>,
+    pub visibility: Option<String>,
+}
+
+/// Per-org overrides, e.g. different include/exclude globs for each org.
+#[derive(Debug, Deserialize)]
+pub struct OrgEntry {
+    pub name: String,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub visibility: Option<String>,
+    pub no_forks: Option<bool>,
+    pub forks_only: Option<bool>,
+    pub no_archived: Option<bool>,
+    pub archived_only: Option<bool>,
+}
+
+const DEFAULT_NAMES: [&str; 2] = ["ghsync.toml", "ghsync.yaml"];
+
+/// Loads `path`, or discovers `ghsync.toml`/`ghsync.yaml` in the CWD. Returns
+/// `Ok(None)` when no path was given and nothing was found, since a config
+/// file is optional.
+pub fn load(path: Option<&Path>) -> Result<Option<Config>> {
+    let found = match path {
+        Some(p) => Some(p.to_path_buf()),
+        None => DEFAULT_NAMES.iter().map(PathBuf::from).find(|p| p.exists()),
+    };
+
+    let Some(found) = found else {
+        return Ok(None);
+    };
+
+    let raw = std::fs::read_to_string(&found)
+        .with_context(|| format!("failed to read config: {}", found.display()))?;
+
+    let config: Config = if found.extension().is_some_and(|e| e == "yaml" || e == "yml") {
+        serde_yaml::from_str(&raw)
+            .with_context(|| format!("failed to parse config: {}", found.display()))?
+    } else {
+        toml::from_str(&raw)
+            .with_context(|| format!("failed to parse config: {}", found.display()))?
+    };
+
+    println!("Loaded config: {}", found.display());
+    Ok(Some(config))
+}
+
+impl Config {
+    /// Per-owner filter overrides, keyed by org/user login, for
+    /// [`crate::github::discover_repos`] to apply on top of the global filters.
+    pub fn org_filters(&self) -> HashMap<String, OrgFilters> {
+        self.organizations
+            .iter()
+            .map(|o| {
+                (
+                    o.name.clone(),
+                    OrgFilters {
+                        patterns: o.include.clone(),
+                        exclude: o.exclude.clone(),
+                        visibility: o.visibility.clone(),
+                        no_forks: o.no_forks,
+                        forks_only: o.forks_only,
+                        no_archived: o.no_archived,
+                        archived_only: o.archived_only,
+                    },
+                )
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,9 @@
+use crate::bundle;
 use anyhow::{bail, Context, Result};
 use serde::Deserialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -28,6 +31,61 @@ pub struct Filters<'a> {
     pub visibility: Option<&'a str>,
     pub patterns: &'a [String],
     pub exclude: &'a [String],
+    /// Per-owner overrides from a config file, merged on top of the fields
+    /// above when that owner's repos are filtered.
+    pub per_owner: &'a HashMap<String, OrgFilters>,
+}
+
+/// Config-file override for a single org/user, merged over the global
+/// [`Filters`] when filtering that owner's repos.
+#[derive(Debug, Default, Clone)]
+pub struct OrgFilters {
+    pub patterns: Vec<String>,
+    pub exclude: Vec<String>,
+    pub visibility: Option<String>,
+    pub no_forks: Option<bool>,
+    pub forks_only: Option<bool>,
+    pub no_archived: Option<bool>,
+    pub archived_only: Option<bool>,
+}
+
+/// Resolved filter values for one owner: global [`Filters`] with any
+/// matching [`OrgFilters`] override applied on top.
+struct EffectiveFilters<'a> {
+    no_forks: bool,
+    forks_only: bool,
+    no_archived: bool,
+    archived_only: bool,
+    visibility: Option<&'a str>,
+    patterns: Vec<&'a str>,
+    exclude: Vec<&'a str>,
+}
+
+impl<'a> EffectiveFilters<'a> {
+    fn for_owner(filters: &'a Filters<'a>, owner: &str) -> Self {
+        let over = filters.per_owner.get(owner);
+        EffectiveFilters {
+            no_forks: over.and_then(|o| o.no_forks).unwrap_or(filters.no_forks),
+            forks_only: over.and_then(|o| o.forks_only).unwrap_or(filters.forks_only),
+            no_archived: over
+                .and_then(|o| o.no_archived)
+                .unwrap_or(filters.no_archived),
+            archived_only: over
+                .and_then(|o| o.archived_only)
+                .unwrap_or(filters.archived_only),
+            visibility: over
+                .and_then(|o| o.visibility.as_deref())
+                .or(filters.visibility),
+            patterns: over
+                .map(|o| o.patterns.iter().map(String::as_str).collect())
+                .filter(|p: &Vec<&str>| !p.is_empty())
+                .unwrap_or_else(|| filters.patterns.iter().map(String::as_str).collect()),
+            exclude: over
+                .map(|o| o.exclude.iter().map(String::as_str).collect())
+                .filter(|p: &Vec<&str>| !p.is_empty())
+                .unwrap_or_else(|| filters.exclude.iter().map(String::as_str).collect()),
+        }
+    }
 }
 
 pub fn run_cmd(cmd: &[&str]) -> Result<String> {
@@ -118,58 +176,59 @@ pub fn discover_repos(filters: &Filters, username: &str, orgs: &[String]) -> Res
 
     let mut seen = HashMap::new();
     for owner in &owners {
-        let repos = list_repos(owner)?;
-        for repo in repos {
-            seen.entry(repo.name_with_owner.clone())
-                .or_insert(repo);
+        let mut repos = list_repos(owner)?;
+        let eff = EffectiveFilters::for_owner(filters, owner);
+
+        if eff.no_forks {
+            let before = repos.len();
+            repos.retain(|r| !r.is_fork);
+            let excluded = before - repos.len();
+            if excluded > 0 {
+                println!("Excluded {excluded} fork(s) from {owner}");
+            }
         }
-    }
 
-    let mut repos: Vec<Repo> = seen.into_values().collect();
+        if eff.forks_only {
+            repos.retain(|r| r.is_fork);
+        }
 
-    if filters.no_forks {
-        let before = repos.len();
-        repos.retain(|r| !r.is_fork);
-        let excluded = before - repos.len();
-        if excluded > 0 {
-            println!("Excluded {excluded} fork(s)");
+        if eff.no_archived {
+            let before = repos.len();
+            repos.retain(|r| !r.is_archived);
+            let excluded = before - repos.len();
+            if excluded > 0 {
+                println!("Excluded {excluded} archived repo(s) from {owner}");
+            }
         }
-    }
 
-    if filters.forks_only {
-        repos.retain(|r| r.is_fork);
-    }
+        if eff.archived_only {
+            repos.retain(|r| r.is_archived);
+        }
 
-    if filters.no_archived {
-        let before = repos.len();
-        repos.retain(|r| !r.is_archived);
-        let excluded = before - repos.len();
-        if excluded > 0 {
-            println!("Excluded {excluded} archived repo(s)");
+        if let Some(vis) = eff.visibility {
+            repos.retain(|r| r.visibility.eq_ignore_ascii_case(vis));
         }
-    }
 
-    if filters.archived_only {
-        repos.retain(|r| r.is_archived);
-    }
+        if !eff.patterns.is_empty() {
+            repos.retain(|r| {
+                let name = r.name_with_owner.split_once('/').map(|(_, n)| n).unwrap_or("");
+                eff.patterns.iter().any(|p| glob_match(p, name))
+            });
+        }
 
-    if let Some(vis) = filters.visibility {
-        repos.retain(|r| r.visibility.eq_ignore_ascii_case(vis));
-    }
+        if !eff.exclude.is_empty() {
+            repos.retain(|r| {
+                let name = r.name_with_owner.split_once('/').map(|(_, n)| n).unwrap_or("");
+                !eff.exclude.iter().any(|p| glob_match(p, name))
+            });
+        }
 
-    if !filters.patterns.is_empty() {
-        repos.retain(|r| {
-            let name = r.name_with_owner.split_once('/').map(|(_, n)| n).unwrap_or("");
-            filters.patterns.iter().any(|p| glob_match(p, name))
-        });
+        for repo in repos {
+            seen.entry(repo.name_with_owner.clone()).or_insert(repo);
+        }
     }
 
-    if !filters.exclude.is_empty() {
-        repos.retain(|r| {
-            let name = r.name_with_owner.split_once('/').map(|(_, n)| n).unwrap_or("");
-            !filters.exclude.iter().any(|p| glob_match(p, name))
-        });
-    }
+    let mut repos: Vec<Repo> = seen.into_values().collect();
 
     repos.sort_by(|a, b| {
         a.name_with_owner
@@ -180,6 +239,92 @@ pub fn discover_repos(filters: &Filters, username: &str, orgs: &[String]) -> Res
     Ok(repos)
 }
 
+/// Calls a paginated `gh api` list endpoint and flattens every page into a
+/// single JSON array, since `gh api --paginate` prints one page per line
+/// rather than one merged document.
+fn api_paginated(endpoint: &str) -> Result<Vec<Value>> {
+    let output = run_cmd(&["gh", "api", endpoint, "--paginate"])?;
+    let mut values = Vec::new();
+    for page in serde_json::Deserializer::from_str(&output).into_iter::<Value>() {
+        match page? {
+            Value::Array(items) => values.extend(items),
+            other => values.push(other),
+        }
+    }
+    Ok(values)
+}
+
+fn write_metadata_json(meta_dir: &Path, name: &str, value: &Value) -> Result<std::path::PathBuf> {
+    let path = meta_dir.join(format!("{name}.json"));
+    let json = serde_json::to_string_pretty(value)?;
+    std::fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Exports everything git mirroring can't capture — issues, PRs, releases,
+/// labels, topics/description, and optionally the wiki — to
+/// `<repo_dir>/.ghsync/*.json`, gated behind `--include-metadata` /
+/// `--include-wiki`.
+pub fn export_metadata(
+    repo: &Repo,
+    repo_dir: &Path,
+    include_wiki: bool,
+) -> Result<Vec<std::path::PathBuf>> {
+    let nwo = repo.name_with_owner.as_str();
+    let (owner, name) = nwo.split_once('/').unwrap_or(("", nwo));
+    let meta_dir = repo_dir.join(".ghsync");
+    std::fs::create_dir_all(&meta_dir)
+        .with_context(|| format!("failed to create {}", meta_dir.display()))?;
+
+    let mut written = Vec::new();
+
+    let summary = api_paginated(&format!("repos/{owner}/{name}"))?;
+    if let Some(value) = summary.first() {
+        written.push(write_metadata_json(&meta_dir, "repo", value)?);
+    }
+
+    for (file, endpoint) in [
+        ("issues", format!("repos/{owner}/{name}/issues?state=all")),
+        ("pulls", format!("repos/{owner}/{name}/pulls?state=all")),
+        ("releases", format!("repos/{owner}/{name}/releases")),
+        ("labels", format!("repos/{owner}/{name}/labels")),
+    ] {
+        let items = api_paginated(&endpoint)?;
+        written.push(write_metadata_json(&meta_dir, file, &Value::Array(items))?);
+    }
+
+    if include_wiki {
+        let wiki_url = repo.ssh_url.replacen(".git", ".wiki.git", 1);
+        let wiki_dir = meta_dir.join("wiki");
+        let synced = if wiki_dir.exists() {
+            run_cmd(&["git", "-C", &wiki_dir.display().to_string(), "remote", "update"]).is_ok()
+        } else {
+            // Wikis are disabled on most repos, so a failed clone here is
+            // expected and not treated as an error.
+            run_cmd(&[
+                "git",
+                "clone",
+                "--mirror",
+                wiki_url.as_str(),
+                &wiki_dir.display().to_string(),
+            ])
+            .is_ok()
+        };
+
+        // Bundle the wiki into a single file so it gets a Metadata-kind
+        // manifest entry like the JSON exports, instead of being left
+        // unverifiable.
+        if synced {
+            let wiki_bundle = meta_dir.join("wiki.bundle");
+            if bundle::create_bundle(&wiki_dir, &wiki_bundle).is_ok() {
+                written.push(wiki_bundle);
+            }
+        }
+    }
+
+    Ok(written)
+}
+
 fn glob_match(pattern: &str, text: &str) -> bool {
     glob_match_bytes(pattern.to_lowercase().as_bytes(), text.to_lowercase().as_bytes())
 }
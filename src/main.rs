@@ -1,6 +1,11 @@
 mod backup;
+mod bundle;
 mod cli;
+mod config;
 mod github;
+mod manifest;
+mod repair;
+mod s3;
 
 fn main() {
     if let Err(e) = cli::run() {
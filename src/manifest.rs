@@ -0,0 +1,119 @@
+use crate::bundle;
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const MANIFEST_FILE: &str = "ghsync-manifest.json";
+
+/// What kind of artifact an entry's path points at, so `verify` knows how
+/// to recompute its digest.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    Bundle,
+    Clone,
+    /// A single metadata file (issues/PRs/releases/etc. JSON, or a wiki
+    /// clone bundle), hashed the same way as `Bundle`.
+    Metadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path to the artifact, relative to the manifest's destination dir.
+    pub path: PathBuf,
+    pub kind: ArtifactKind,
+    /// `sha512-<base64>`, following the cacache SRI scheme.
+    pub integrity: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub entries: BTreeMap<String, ManifestEntry>,
+}
+
+pub fn sri_of_bytes(bytes: &[u8]) -> String {
+    let digest = Sha512::digest(bytes);
+    format!(
+        "sha512-{}",
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    )
+}
+
+pub fn sri_of_file(path: &Path) -> Result<String> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(sri_of_bytes(&bytes))
+}
+
+static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Deterministic content hash for a mirror/regular clone: hash a `git
+/// bundle` of every ref rather than the working tree, so re-cloning the
+/// same refs yields the same digest regardless of filesystem timestamps.
+pub fn sri_of_clone(repo_dir: &Path) -> Result<String> {
+    // The manifest worker pool runs this concurrently for different repos,
+    // so the scratch path must be unique per call, not just per process.
+    let seq = SCRATCH_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let tmp = std::env::temp_dir().join(format!(
+        "ghsync-manifest-{}-{seq}.bundle",
+        std::process::id()
+    ));
+    bundle::create_bundle(repo_dir, &tmp)?;
+    let sri = sri_of_file(&tmp);
+    let _ = std::fs::remove_file(&tmp);
+    sri
+}
+
+pub fn write(dest: &Path, manifest: &Manifest) -> Result<()> {
+    let path = dest.join(MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(manifest)?;
+    std::fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
+
+pub fn load(dest: &Path) -> Result<Manifest> {
+    let path = dest.join(MANIFEST_FILE);
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+pub enum VerifyOutcome {
+    Ok,
+    Mismatch,
+    Missing,
+}
+
+impl VerifyOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VerifyOutcome::Ok => "ok",
+            VerifyOutcome::Mismatch => "mismatch",
+            VerifyOutcome::Missing => "missing",
+        }
+    }
+}
+
+/// Recomputes `entry`'s digest relative to `dest` and compares it to the
+/// recorded integrity string.
+pub fn verify_entry(dest: &Path, entry: &ManifestEntry) -> VerifyOutcome {
+    let path = dest.join(&entry.path);
+    if !path.exists() {
+        return VerifyOutcome::Missing;
+    }
+
+    let actual = match entry.kind {
+        ArtifactKind::Bundle | ArtifactKind::Metadata => sri_of_file(&path),
+        ArtifactKind::Clone => sri_of_clone(&path),
+    };
+
+    match actual {
+        Ok(sri) if sri == entry.integrity => VerifyOutcome::Ok,
+        Ok(_) => VerifyOutcome::Mismatch,
+        Err(_) => VerifyOutcome::Missing,
+    }
+}
@@ -0,0 +1,180 @@
+use crate::backup::{BackupResult, Status};
+use crate::github::run_cmd;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+struct Target {
+    nwo: String,
+    dir: PathBuf,
+}
+
+/// A directory is a repair target only if it's actually a git repo — a
+/// `<owner>/<name>/` produced by `--format bundle --include-metadata`
+/// holds nothing but a `.ghsync/` metadata export (the real artifact is
+/// the sibling `.bundle` file, and the live mirror lives under
+/// `.ghsync-cache/`), and must not be treated as a broken clone.
+fn is_git_repo_dir(dir: &Path) -> bool {
+    dir.join(".git").exists() || (dir.join("HEAD").exists() && dir.join("objects").is_dir())
+}
+
+/// Walks `dest` for `<owner>/<name>` directories, skipping the bundle-mode
+/// cache and any non-repo entries.
+fn discover_targets(dest: &Path) -> Result<Vec<Target>> {
+    let mut targets = Vec::new();
+    for owner_entry in std::fs::read_dir(dest)?.flatten() {
+        if !owner_entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let owner = owner_entry.file_name().to_string_lossy().to_string();
+        if owner == ".ghsync-cache" {
+            continue;
+        }
+        for entry in std::fs::read_dir(owner_entry.path())?.flatten() {
+            if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let path = entry.path();
+            if !is_git_repo_dir(&path) {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            targets.push(Target {
+                nwo: format!("{owner}/{name}"),
+                dir: path,
+            });
+        }
+    }
+    Ok(targets)
+}
+
+fn fetch_cmd(dir: &str, is_bare: bool) -> Vec<&str> {
+    if is_bare {
+        vec!["git", "-C", dir, "remote", "update"]
+    } else {
+        vec!["git", "-C", dir, "fetch", "--all"]
+    }
+}
+
+/// Runs `git fsck --full` (plus `rev-parse --verify HEAD` for non-bare
+/// trees) and reports whether the repo's objects are intact.
+fn fsck_and_head_ok(dir: &str, is_bare: bool) -> bool {
+    let fsck_ok = run_cmd(&["git", "-C", dir, "fsck", "--full"]).is_ok();
+    let head_ok = is_bare || run_cmd(&["git", "-C", dir, "rev-parse", "--verify", "HEAD"]).is_ok();
+    fsck_ok && head_ok
+}
+
+/// Classifies one repo directory via [`fsck_and_head_ok`] and repairs it
+/// if broken: first a plain re-fetch, re-checking `fsck` afterwards, and
+/// if that still doesn't resolve it, a full re-clone from the remote.
+fn repair_one(target: &Target) -> BackupResult {
+    let dir = target.dir.display().to_string();
+    let is_bare = run_cmd(&["git", "-C", &dir, "rev-parse", "--is-bare-repository"])
+        .map(|s| s.trim() == "true")
+        .unwrap_or(false);
+
+    // (status, error message) rather than a plain Result, since
+    // `FetchStale` needs to carry a message without being treated as a
+    // hard failure by `run_repair`'s summary.
+    let (status, error): (Status, Option<String>) = if fsck_and_head_ok(&dir, is_bare) {
+        // Objects are already sound — a failed fetch here is a transient
+        // network/remote issue, not corruption, so it must not be folded
+        // into `Status::Failed` (which `run_repair` treats as a hard error).
+        match run_cmd(&fetch_cmd(&dir, is_bare)) {
+            Ok(_) => (Status::Healthy, None),
+            Err(e) => (Status::FetchStale, Some(e.to_string())),
+        }
+    } else if run_cmd(&fetch_cmd(&dir, is_bare)).is_ok() && fsck_and_head_ok(&dir, is_bare) {
+        (Status::Repaired, None)
+    } else {
+        let reclone = std::fs::remove_dir_all(&target.dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                let mut args = vec!["gh", "repo", "clone", target.nwo.as_str(), dir.as_str(), "--"];
+                if is_bare {
+                    args.push("--mirror");
+                }
+                run_cmd(&args).map_err(|e| e.to_string())
+            });
+        match reclone {
+            Ok(_) => (Status::Repaired, None),
+            Err(e) => (Status::Failed, Some(e)),
+        }
+    };
+
+    BackupResult {
+        nwo: target.nwo.clone(),
+        status,
+        error,
+        upload_error: None,
+        manifest_entry: None,
+        metadata_entries: Vec::new(),
+    }
+}
+
+struct Progress {
+    count: usize,
+    results: Vec<BackupResult>,
+}
+
+/// Repairs every repo directory under `dest`, reusing the same
+/// `thread::scope` worker pool pattern as [`crate::backup::run_backup`].
+pub fn run_repair(dest: &Path, jobs: usize) -> Result<()> {
+    let dest = std::fs::canonicalize(dest)?;
+    let targets = discover_targets(&dest)?;
+    let total = targets.len();
+    println!("\nChecking: {} (repo(s): {total}, workers: {jobs})", dest.display());
+
+    let next_idx = AtomicUsize::new(0);
+    let progress = Mutex::new(Progress {
+        count: 0,
+        results: Vec::with_capacity(total),
+    });
+
+    std::thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let idx = next_idx.fetch_add(1, Ordering::SeqCst);
+                if idx >= total {
+                    break;
+                }
+                let result = repair_one(&targets[idx]);
+                let mut p = progress.lock().unwrap();
+                p.count += 1;
+                let i = p.count;
+                println!("  [{i}/{total}] [{}] {}", result.status.icon(), result.nwo);
+                if let Some(ref e) = result.error {
+                    if let Some(first_line) = e.lines().next() {
+                        println!("           {first_line}");
+                    }
+                }
+                p.results.push(result);
+            });
+        }
+    });
+
+    let results = progress.into_inner().unwrap().results;
+    let healthy = results.iter().filter(|r| matches!(r.status, Status::Healthy)).count();
+    let repaired = results.iter().filter(|r| matches!(r.status, Status::Repaired)).count();
+    let fetch_stale: Vec<_> = results.iter().filter(|r| matches!(r.status, Status::FetchStale)).collect();
+    let failed: Vec<_> = results.iter().filter(|r| matches!(r.status, Status::Failed)).collect();
+
+    println!("\n--- Summary ---");
+    println!("  Healthy:  {healthy}");
+    println!("  Repaired: {repaired}");
+    if !fetch_stale.is_empty() {
+        println!("  Fetch stale: {}", fetch_stale.len());
+    }
+    println!("  Failed:   {}", failed.len());
+
+    if !failed.is_empty() {
+        println!("\nFailed repos:");
+        for r in &failed {
+            println!("  - {}", r.nwo);
+        }
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::github::run_cmd;
+use anyhow::Result;
+use std::path::Path;
+
+/// S3-compatible sink (Garage/MinIO/AWS), reached by shelling out to the
+/// `aws` CLI the same way the rest of ghsync shells out to `git`/`gh`.
+/// Credentials are picked up from the environment by the `aws` CLI itself.
+pub struct S3Config {
+    pub endpoint: Option<String>,
+    pub bucket: String,
+}
+
+impl S3Config {
+    /// Uploads `path` to `<owner>/<name><suffix>` so the bucket mirrors the
+    /// local `<owner>/<name>` layout.
+    pub fn upload(&self, path: &Path, owner: &str, name: &str, suffix: &str) -> Result<()> {
+        let key = format!("{owner}/{name}{suffix}");
+        let uri = format!("s3://{}/{key}", self.bucket);
+
+        let mut args = vec![
+            "aws".to_string(),
+            "s3".to_string(),
+            "cp".to_string(),
+            path.display().to_string(),
+            uri,
+        ];
+        if let Some(endpoint) = &self.endpoint {
+            args.push("--endpoint-url".to_string());
+            args.push(endpoint.clone());
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_cmd(&args)?;
+        Ok(())
+    }
+
+    /// Tars `dir` (a mirror/regular clone) to a temp file, then uploads it.
+    pub fn upload_dir(&self, dir: &Path, owner: &str, name: &str) -> Result<()> {
+        let tmp = std::env::temp_dir().join(format!("ghsync-{owner}-{name}.tar.gz"));
+        let parent = dir.parent().map(|p| p.display().to_string()).unwrap_or_default();
+        let dir_name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        run_cmd(&[
+            "tar",
+            "-czf",
+            &tmp.display().to_string(),
+            "-C",
+            &parent,
+            &dir_name,
+        ])?;
+
+        let result = self.upload(&tmp, owner, name, ".tar.gz");
+        let _ = std::fs::remove_file(&tmp);
+        result
+    }
+}